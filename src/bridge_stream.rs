@@ -0,0 +1,268 @@
+use crate::errors::Error;
+use crate::packet::Packet;
+
+use futures::stream::Stream;
+use std::collections::VecDeque;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::SystemTime;
+
+struct BridgedStream<St> {
+    stream: St,
+    buffer: VecDeque<Packet>,
+    newest_timestamp: Option<SystemTime>,
+    exhausted: bool,
+}
+
+/// Merges several `PacketStream`s (e.g. one per interface) into a single
+/// stream of batches ordered by packet timestamp, for correlating traffic
+/// captured on multiple NICs simultaneously.
+pub struct BridgeStream<St> {
+    streams: Vec<BridgedStream<St>>,
+}
+
+impl<St> BridgeStream<St>
+where
+    St: Stream<Item = Result<Vec<Packet>, Error>> + Unpin,
+{
+    pub fn new(streams: Vec<St>) -> BridgeStream<St> {
+        BridgeStream {
+            streams: streams
+                .into_iter()
+                .map(|stream| BridgedStream {
+                    stream,
+                    buffer: VecDeque::new(),
+                    newest_timestamp: None,
+                    exhausted: false,
+                })
+                .collect(),
+        }
+    }
+}
+
+impl<St> Stream for BridgeStream<St>
+where
+    St: Stream<Item = Result<Vec<Packet>, Error>> + Unpin,
+{
+    type Item = Result<Vec<Packet>, Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = unsafe { self.get_unchecked_mut() };
+
+        let mut waiting_on_empty_buffer = false;
+        for bridged in this.streams.iter_mut() {
+            if bridged.exhausted {
+                continue;
+            }
+            match Pin::new(&mut bridged.stream).poll_next(cx) {
+                Poll::Ready(Some(Ok(packets))) => {
+                    for packet in packets {
+                        bridged.newest_timestamp = Some(
+                            bridged
+                                .newest_timestamp
+                                .map_or(packet.timestamp(), |newest| newest.max(packet.timestamp())),
+                        );
+                        bridged.buffer.push_back(packet);
+                    }
+                }
+                Poll::Ready(Some(Err(e))) => return Poll::Ready(Some(Err(e))),
+                Poll::Ready(None) => bridged.exhausted = true,
+                Poll::Pending => {
+                    if bridged.buffer.is_empty() {
+                        waiting_on_empty_buffer = true;
+                    }
+                }
+            }
+        }
+
+        if this.streams.iter().all(|b| b.exhausted && b.buffer.is_empty()) {
+            return Poll::Ready(None);
+        }
+
+        // A live stream with nothing buffered yet could still produce a
+        // packet older than anything we've seen; wait rather than guess.
+        if waiting_on_empty_buffer {
+            return Poll::Pending;
+        }
+
+        let all_exhausted = this.streams.iter().all(|b| b.exhausted);
+
+        // Only live streams bound how far we can safely drain: any packet
+        // newer than the oldest "newest buffered timestamp" among them could
+        // still be beaten by one still to arrive.
+        let horizon = this
+            .streams
+            .iter()
+            .filter(|b| !b.exhausted)
+            .filter_map(|b| b.newest_timestamp);
+        let horizon = horizon.min();
+
+        let mut ready = vec![];
+        for bridged in this.streams.iter_mut() {
+            while let Some(front) = bridged.buffer.front() {
+                let emit = all_exhausted || horizon.map_or(false, |h| front.timestamp() <= h);
+                if emit {
+                    ready.push(bridged.buffer.pop_front().unwrap());
+                } else {
+                    break;
+                }
+            }
+        }
+
+        if ready.is_empty() {
+            return Poll::Pending;
+        }
+
+        ready.sort_by_key(|p| p.timestamp());
+
+        Poll::Ready(Some(Ok(ready)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    struct MockStream {
+        responses: VecDeque<Poll<Option<Result<Vec<Packet>, Error>>>>,
+    }
+
+    impl MockStream {
+        fn new(responses: Vec<Poll<Option<Result<Vec<Packet>, Error>>>>) -> Self {
+            MockStream {
+                responses: responses.into_iter().collect(),
+            }
+        }
+    }
+
+    impl Stream for MockStream {
+        type Item = Result<Vec<Packet>, Error>;
+
+        fn poll_next(mut self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+            self.responses.pop_front().unwrap_or(Poll::Ready(None))
+        }
+    }
+
+    fn packet_at(seconds: u64) -> Packet {
+        Packet::new(
+            SystemTime::UNIX_EPOCH + Duration::from_secs(seconds),
+            4,
+            4,
+            vec![0u8; 4],
+        )
+    }
+
+    fn noop_cx<'a>() -> Context<'a> {
+        Context::from_waker(futures::task::noop_waker_ref())
+    }
+
+    fn drain<St: Stream<Item = Result<Vec<Packet>, Error>> + Unpin>(
+        mut bridge: BridgeStream<St>,
+    ) -> Vec<Packet> {
+        let mut cx = noop_cx();
+        let mut seen = vec![];
+        loop {
+            match Pin::new(&mut bridge).poll_next(&mut cx) {
+                Poll::Ready(Some(Ok(packets))) => seen.extend(packets),
+                Poll::Ready(Some(Err(e))) => panic!("Unexpected error: {}", e),
+                Poll::Ready(None) => break,
+                Poll::Pending => continue,
+            }
+        }
+        seen
+    }
+
+    #[test]
+    fn merges_out_of_order_batches_by_timestamp() {
+        let stream_a = MockStream::new(vec![
+            Poll::Ready(Some(Ok(vec![packet_at(1), packet_at(3)]))),
+            Poll::Ready(None),
+        ]);
+        let stream_b = MockStream::new(vec![
+            Poll::Ready(Some(Ok(vec![packet_at(2)]))),
+            Poll::Ready(None),
+        ]);
+
+        let seen = drain(BridgeStream::new(vec![stream_a, stream_b]));
+
+        assert_eq!(
+            seen.iter().map(|p| p.timestamp()).collect::<Vec<_>>(),
+            vec![1, 2, 3]
+                .into_iter()
+                .map(|s| SystemTime::UNIX_EPOCH + Duration::from_secs(s))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn waits_on_a_pending_stream_instead_of_reordering() {
+        // stream_a has a packet buffered at t=5; stream_b is still pending and
+        // has produced nothing yet, then later yields an older packet at
+        // t=1. The merge must not emit t=5 before t=1 has had the chance to.
+        let stream_a = MockStream::new(vec![
+            Poll::Ready(Some(Ok(vec![packet_at(5)]))),
+            Poll::Ready(None),
+        ]);
+        let stream_b = MockStream::new(vec![
+            Poll::Pending,
+            Poll::Ready(Some(Ok(vec![packet_at(1)]))),
+            Poll::Ready(None),
+        ]);
+
+        let mut bridge = BridgeStream::new(vec![stream_a, stream_b]);
+        let mut cx = noop_cx();
+
+        // stream_a has t=5 buffered but stream_b is live and empty: nothing
+        // is safe to emit yet.
+        assert!(matches!(
+            Pin::new(&mut bridge).poll_next(&mut cx),
+            Poll::Pending
+        ));
+
+        let seen = drain(bridge);
+
+        assert_eq!(
+            seen.iter().map(|p| p.timestamp()).collect::<Vec<_>>(),
+            vec![1, 5]
+                .into_iter()
+                .map(|s| SystemTime::UNIX_EPOCH + Duration::from_secs(s))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn propagates_errors_immediately() {
+        let stream_a = MockStream::new(vec![Poll::Ready(Some(Err(Error::InvalidArgument(
+            "boom".to_owned(),
+        ))))]);
+        let stream_b = MockStream::new(vec![Poll::Ready(Some(Ok(vec![packet_at(1)])))]);
+
+        let mut bridge = BridgeStream::new(vec![stream_a, stream_b]);
+        let mut cx = noop_cx();
+
+        match Pin::new(&mut bridge).poll_next(&mut cx) {
+            Poll::Ready(Some(Err(Error::InvalidArgument(msg)))) => assert_eq!(msg, "boom"),
+            _ => panic!("Expected propagated error, got a different result"),
+        }
+    }
+
+    #[test]
+    fn flushes_remaining_buffer_once_all_streams_are_exhausted() {
+        let stream_a = MockStream::new(vec![
+            Poll::Ready(Some(Ok(vec![packet_at(10), packet_at(20)]))),
+            Poll::Ready(None),
+        ]);
+        let stream_b = MockStream::new(vec![Poll::Ready(None)]);
+
+        let seen = drain(BridgeStream::new(vec![stream_a, stream_b]));
+
+        assert_eq!(
+            seen.iter().map(|p| p.timestamp()).collect::<Vec<_>>(),
+            vec![10, 20]
+                .into_iter()
+                .map(|s| SystemTime::UNIX_EPOCH + Duration::from_secs(s))
+                .collect::<Vec<_>>()
+        );
+    }
+}
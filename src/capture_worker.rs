@@ -0,0 +1,54 @@
+use crate::config::Config;
+use crate::errors::Error;
+use crate::handle::Handle;
+use crate::packet::Packet;
+use crate::packet_iterator::{PacketIterator, PacketIteratorItem};
+
+use log::*;
+use std::sync::Arc;
+use std::thread;
+use tokio::sync::mpsc;
+
+pub type CaptureReceiver = mpsc::Receiver<Result<Vec<Packet>, Error>>;
+
+/// Runs `handle`'s `pcap_dispatch`/`pcap_next_ex` loop on a single dedicated
+/// OS thread and forwards completed batches over a bounded channel, so a
+/// slow or idle interface blocks that one thread instead of an executor
+/// thread. The channel's capacity gives backpressure. Each call spawns its
+/// own thread, so the number of capture threads grows with the number of
+/// captures; threads are not shared between handles.
+pub fn spawn(config: &Config, handle: Arc<Handle>) -> Result<CaptureReceiver, Error> {
+    let (sender, receiver) = mpsc::channel(config.channel_buffer_size());
+    let mut iter = PacketIterator::new(config, &handle);
+    let idle_backoff = config.timeout();
+
+    thread::Builder::new()
+        .name("pcap-async-capture".to_owned())
+        .spawn(move || {
+            let mut sender = sender;
+            loop {
+                match iter.next() {
+                    None | Some(PacketIteratorItem::Complete) => break,
+                    Some(PacketIteratorItem::NoPackets) => {
+                        // The handle is non-blocking, so an idle interface
+                        // returns immediately; back off instead of busy
+                        // spinning this thread at 100% CPU.
+                        thread::sleep(idle_backoff);
+                    }
+                    Some(PacketIteratorItem::Err(e)) => {
+                        let _ = sender.blocking_send(Err(e));
+                        break;
+                    }
+                    Some(PacketIteratorItem::Packets(packets)) => {
+                        if sender.blocking_send(Ok(packets)).is_err() {
+                            debug!("Capture consumer dropped, stopping capture thread");
+                            break;
+                        }
+                    }
+                }
+            }
+        })
+        .map_err(|e| Error::InvalidArgument(e.to_string()))?;
+
+    Ok(receiver)
+}
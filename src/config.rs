@@ -0,0 +1,123 @@
+use std::time::Duration;
+
+/// Timestamp resolution requested for a capture, mirroring libpcap's
+/// `pcap_set_tstamp_precision` precisions.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TimestampPrecision {
+    Micro,
+    Nano,
+}
+
+impl Default for TimestampPrecision {
+    fn default() -> Self {
+        TimestampPrecision::Micro
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Config {
+    snaplen: i32,
+    buffer_size: i32,
+    timeout: Duration,
+    max_packets_read: usize,
+    bpf: Option<String>,
+    tstamp_precision: TimestampPrecision,
+    channel_buffer_size: usize,
+    datalink: Option<i32>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            snaplen: 65535,
+            buffer_size: 16_777_216,
+            timeout: Duration::from_millis(10),
+            max_packets_read: 1000,
+            bpf: None,
+            tstamp_precision: TimestampPrecision::default(),
+            channel_buffer_size: 100,
+            datalink: None,
+        }
+    }
+}
+
+impl Config {
+    pub fn snaplen(&self) -> i32 {
+        self.snaplen
+    }
+
+    pub fn with_snaplen(&mut self, snaplen: i32) -> &mut Self {
+        self.snaplen = snaplen;
+        self
+    }
+
+    pub fn buffer_size(&self) -> i32 {
+        self.buffer_size
+    }
+
+    pub fn with_buffer_size(&mut self, buffer_size: i32) -> &mut Self {
+        self.buffer_size = buffer_size;
+        self
+    }
+
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    pub fn with_timeout(&mut self, timeout: Duration) -> &mut Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn max_packets_read(&self) -> usize {
+        self.max_packets_read
+    }
+
+    pub fn with_max_packets_read(&mut self, max_packets_read: usize) -> &mut Self {
+        self.max_packets_read = max_packets_read;
+        self
+    }
+
+    pub fn bpf(&self) -> Option<String> {
+        self.bpf.clone()
+    }
+
+    pub fn with_bpf(&mut self, bpf: String) -> &mut Self {
+        self.bpf = Some(bpf);
+        self
+    }
+
+    /// Timestamp precision to request on activation. Defaults to
+    /// microsecond, which is what every handle supports; nanosecond is only
+    /// honored on live captures whose NIC/driver can provide it.
+    pub fn tstamp_precision(&self) -> TimestampPrecision {
+        self.tstamp_precision
+    }
+
+    pub fn with_tstamp_precision(&mut self, tstamp_precision: TimestampPrecision) -> &mut Self {
+        self.tstamp_precision = tstamp_precision;
+        self
+    }
+
+    /// Capacity of the channel the capture worker thread uses to hand
+    /// batches of packets to the async `PacketStream`.
+    pub fn channel_buffer_size(&self) -> usize {
+        self.channel_buffer_size
+    }
+
+    pub fn with_channel_buffer_size(&mut self, channel_buffer_size: usize) -> &mut Self {
+        self.channel_buffer_size = channel_buffer_size;
+        self
+    }
+
+    /// Link-layer header type to switch the handle to on activation, from
+    /// the set `Handle::list_datalinks` reports as supported.
+    pub fn datalink(&self) -> Option<i32> {
+        self.datalink
+    }
+
+    pub fn with_datalink(&mut self, datalink: i32) -> &mut Self {
+        self.datalink = Some(datalink);
+        self
+    }
+}
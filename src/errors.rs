@@ -0,0 +1,17 @@
+use failure::Fail;
+
+#[derive(Debug, Fail)]
+pub enum Error {
+    #[fail(display = "Invalid interface: {}", _0)]
+    InvalidInterface(String),
+    #[fail(display = "Libpcap error: {}", _0)]
+    LibPcapError(String),
+    #[fail(display = "Invalid argument: {}", _0)]
+    InvalidArgument(String),
+}
+
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        Error::InvalidArgument(e.to_string())
+    }
+}
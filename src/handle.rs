@@ -0,0 +1,237 @@
+use crate::config::TimestampPrecision;
+use crate::errors::Error;
+use crate::pcap_util;
+use crate::stats::Stats;
+
+use std::ffi::{CStr, CString};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Owns a libpcap handle for either a live interface or an offline savefile.
+pub struct Handle {
+    handle: *mut pcap_sys::pcap_t,
+    live_capture: bool,
+    interrupted: AtomicBool,
+}
+
+unsafe impl Send for Handle {}
+unsafe impl Sync for Handle {}
+
+impl Handle {
+    /// Opens the interface libpcap considers the default for this host.
+    pub fn lookup() -> Result<Arc<Handle>, Error> {
+        let mut err_buf = [0i8; 256];
+        let device = unsafe { pcap_sys::pcap_lookupdev(err_buf.as_mut_ptr()) };
+        if device.is_null() {
+            return Err(Error::InvalidInterface(
+                unsafe { CStr::from_ptr(err_buf.as_ptr()) }
+                    .to_string_lossy()
+                    .into_owned(),
+            ));
+        }
+        let device = unsafe { CStr::from_ptr(device) }.to_string_lossy().into_owned();
+        Self::live_capture(&device)
+    }
+
+    /// Opens `device` for a live capture. The handle is not yet activated;
+    /// callers configure it (snaplen, promiscuous mode, etc.) and call
+    /// `activate` themselves, as `PacketStream::new` does.
+    pub fn live_capture(device: &str) -> Result<Arc<Handle>, Error> {
+        let mut err_buf = [0i8; 256];
+        let c_device = CString::new(device).map_err(|e| Error::InvalidArgument(e.to_string()))?;
+        let handle = unsafe { pcap_sys::pcap_create(c_device.as_ptr(), err_buf.as_mut_ptr()) };
+        if handle.is_null() {
+            return Err(Error::LibPcapError(
+                unsafe { CStr::from_ptr(err_buf.as_ptr()) }
+                    .to_string_lossy()
+                    .into_owned(),
+            ));
+        }
+        Ok(Arc::new(Handle {
+            handle,
+            live_capture: true,
+            interrupted: AtomicBool::new(false),
+        }))
+    }
+
+    /// Opens a `.pcap`/`.pcapng` file for offline replay.
+    pub fn file_capture(path: &str) -> Result<Arc<Handle>, Error> {
+        let mut err_buf = [0i8; 256];
+        let c_path = CString::new(path).map_err(|e| Error::InvalidArgument(e.to_string()))?;
+        let handle = unsafe { pcap_sys::pcap_open_offline(c_path.as_ptr(), err_buf.as_mut_ptr()) };
+        if handle.is_null() {
+            return Err(Error::LibPcapError(
+                unsafe { CStr::from_ptr(err_buf.as_ptr()) }
+                    .to_string_lossy()
+                    .into_owned(),
+            ));
+        }
+        Ok(Arc::new(Handle {
+            handle,
+            live_capture: false,
+            interrupted: AtomicBool::new(false),
+        }))
+    }
+
+    pub fn as_mut_ptr(&self) -> *mut pcap_sys::pcap_t {
+        self.handle
+    }
+
+    pub fn is_live_capture(&self) -> bool {
+        self.live_capture
+    }
+
+    /// Breaks any in-progress `pcap_dispatch`/`pcap_next_ex` loop and marks
+    /// the handle so running loops stop pulling further packets.
+    pub fn interrupt(&self) {
+        self.interrupted.store(true, Ordering::SeqCst);
+        unsafe { pcap_sys::pcap_breakloop(self.handle) };
+    }
+
+    pub fn interrupted(&self) -> bool {
+        self.interrupted.load(Ordering::SeqCst)
+    }
+
+    pub fn set_snaplen(&self, snaplen: i32) -> Result<&Self, Error> {
+        let code = unsafe { pcap_sys::pcap_set_snaplen(self.handle, snaplen) };
+        if code != 0 {
+            Err(pcap_util::convert_libpcap_error(self.handle))
+        } else {
+            Ok(self)
+        }
+    }
+
+    pub fn set_non_block(&self) -> Result<&Self, Error> {
+        let mut err_buf = [0i8; 256];
+        let code = unsafe { pcap_sys::pcap_setnonblock(self.handle, 1, err_buf.as_mut_ptr()) };
+        if code != 0 {
+            Err(pcap_util::convert_libpcap_error(self.handle))
+        } else {
+            Ok(self)
+        }
+    }
+
+    pub fn set_promiscuous(&self) -> Result<&Self, Error> {
+        let code = unsafe { pcap_sys::pcap_set_promisc(self.handle, 1) };
+        if code != 0 {
+            Err(pcap_util::convert_libpcap_error(self.handle))
+        } else {
+            Ok(self)
+        }
+    }
+
+    pub fn set_timeout(&self, timeout: Duration) -> Result<&Self, Error> {
+        let code = unsafe { pcap_sys::pcap_set_timeout(self.handle, timeout.as_millis() as _) };
+        if code != 0 {
+            Err(pcap_util::convert_libpcap_error(self.handle))
+        } else {
+            Ok(self)
+        }
+    }
+
+    pub fn set_buffer_size(&self, buffer_size: i32) -> Result<&Self, Error> {
+        let code = unsafe { pcap_sys::pcap_set_buffer_size(self.handle, buffer_size) };
+        if code != 0 {
+            Err(pcap_util::convert_libpcap_error(self.handle))
+        } else {
+            Ok(self)
+        }
+    }
+
+    /// Requests the given timestamp precision. Must be called before
+    /// `activate`; has no effect on offline captures, whose precision is
+    /// fixed by the file they were written with.
+    pub fn set_tstamp_precision(&self, precision: TimestampPrecision) -> Result<&Self, Error> {
+        let pcap_precision = match precision {
+            TimestampPrecision::Micro => pcap_sys::PCAP_TSTAMP_PRECISION_MICRO,
+            TimestampPrecision::Nano => pcap_sys::PCAP_TSTAMP_PRECISION_NANO,
+        };
+        let code = unsafe { pcap_sys::pcap_set_tstamp_precision(self.handle, pcap_precision as _) };
+        if code != 0 {
+            Err(pcap_util::convert_libpcap_error(self.handle))
+        } else {
+            Ok(self)
+        }
+    }
+
+    /// Samples libpcap's running capture/drop counters for this handle.
+    pub fn stats(&self) -> Result<Stats, Error> {
+        let mut stats: pcap_sys::pcap_stat = unsafe { std::mem::zeroed() };
+        let code = unsafe { pcap_sys::pcap_stats(self.handle, &mut stats as *mut _) };
+        if code != 0 {
+            Err(pcap_util::convert_libpcap_error(self.handle))
+        } else {
+            Ok(Stats::new(stats.ps_recv, stats.ps_drop, stats.ps_ifdrop))
+        }
+    }
+
+    pub fn activate(&self) -> Result<&Self, Error> {
+        let code = unsafe { pcap_sys::pcap_activate(self.handle) };
+        if code < 0 {
+            Err(pcap_util::convert_libpcap_error(self.handle))
+        } else {
+            Ok(self)
+        }
+    }
+
+    /// Lists the link-layer header types this (already activated) handle can
+    /// be switched between with `set_datalink`.
+    pub fn list_datalinks(&self) -> Result<Vec<i32>, Error> {
+        let mut dlt_buf: *mut i32 = std::ptr::null_mut();
+        let n = unsafe { pcap_sys::pcap_list_datalinks(self.handle, &mut dlt_buf as *mut *mut i32) };
+        if n < 0 {
+            Err(pcap_util::convert_libpcap_error(self.handle))
+        } else {
+            let dlts = unsafe { std::slice::from_raw_parts(dlt_buf, n as usize) }.to_vec();
+            unsafe { pcap_sys::pcap_free_datalinks(dlt_buf) };
+            Ok(dlts)
+        }
+    }
+
+    /// Switches the handle's link-layer header type to one from
+    /// `list_datalinks`.
+    pub fn set_datalink(&self, dlt: i32) -> Result<&Self, Error> {
+        let code = unsafe { pcap_sys::pcap_set_datalink(self.handle, dlt) };
+        if code != 0 {
+            Err(pcap_util::convert_libpcap_error(self.handle))
+        } else {
+            Ok(self)
+        }
+    }
+
+    /// The link-layer header type currently in effect, needed to know how to
+    /// decode `Packet::data()`.
+    pub fn datalink(&self) -> i32 {
+        unsafe { pcap_sys::pcap_datalink(self.handle) }
+    }
+
+    pub fn compile_bpf(&self, bpf: String) -> Result<pcap_sys::bpf_program, Error> {
+        let c_bpf = CString::new(bpf).map_err(|e| Error::InvalidArgument(e.to_string()))?;
+        let mut program: pcap_sys::bpf_program = unsafe { std::mem::zeroed() };
+        let code = unsafe {
+            pcap_sys::pcap_compile(self.handle, &mut program as *mut _, c_bpf.as_ptr(), 1, 0)
+        };
+        if code != 0 {
+            Err(pcap_util::convert_libpcap_error(self.handle))
+        } else {
+            Ok(program)
+        }
+    }
+
+    pub fn set_bpf(&self, mut bpf: pcap_sys::bpf_program) -> Result<&Self, Error> {
+        let code = unsafe { pcap_sys::pcap_setfilter(self.handle, &mut bpf as *mut _) };
+        unsafe { pcap_sys::pcap_freecode(&mut bpf as *mut _) };
+        if code != 0 {
+            Err(pcap_util::convert_libpcap_error(self.handle))
+        } else {
+            Ok(self)
+        }
+    }
+}
+
+impl Drop for Handle {
+    fn drop(&mut self) {
+        unsafe { pcap_sys::pcap_close(self.handle) };
+    }
+}
@@ -23,18 +23,22 @@
 #![allow(dead_code, unused_imports)]
 pub mod bpf;
 mod bridge_stream;
+mod capture_worker;
 mod config;
 pub mod errors;
 mod handle;
 mod info;
 mod packet;
+mod packet_writer;
 pub mod pcap_util;
 mod stats;
+mod stats_stream;
 mod stream;
 
 pub use crate::{
     bridge_stream::BridgeStream, config::Config, errors::Error, handle::Handle, info::Info,
-    packet::Packet, stats::Stats, stream::PacketStream, stream::StreamItem,
+    packet::Packet, packet_writer::PacketWriter, stats::Stats, stats_stream::StatsStream,
+    stream::PacketStream, stream::StreamItem,
 };
 pub use byteorder::{BigEndian, LittleEndian, NativeEndian, WriteBytesExt};
 use log::*;
@@ -0,0 +1,132 @@
+use crate::config::TimestampPrecision;
+use crate::errors::Error;
+
+use byteorder::{ByteOrder, WriteBytesExt};
+use std::time::SystemTime;
+
+/// A single captured packet along with the metadata libpcap reported for it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Packet {
+    timestamp: SystemTime,
+    length: u32,
+    actual_length: u32,
+    data: Vec<u8>,
+}
+
+impl Packet {
+    pub fn new(timestamp: SystemTime, length: u32, actual_length: u32, data: Vec<u8>) -> Packet {
+        Packet {
+            timestamp,
+            length,
+            actual_length,
+            data,
+        }
+    }
+
+    /// Time the packet was captured.
+    pub fn timestamp(&self) -> SystemTime {
+        self.timestamp
+    }
+
+    /// Number of bytes actually captured, i.e. `data().len()`.
+    pub fn length(&self) -> u32 {
+        self.length
+    }
+
+    /// Original, on-the-wire length of the packet before any snaplen truncation.
+    pub fn actual_length(&self) -> u32 {
+        self.actual_length
+    }
+
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Serializes this packet as a pcap per-packet record (header + data), as
+    /// written after a pcap global header in a classic `.pcap` file.
+    ///
+    /// `precision` must match the precision of the global header the record
+    /// is written under: the fractional field holds microseconds or
+    /// nanoseconds depending on it.
+    pub fn into_pcap_record<B: ByteOrder>(
+        &self,
+        precision: TimestampPrecision,
+    ) -> Result<Vec<u8>, Error> {
+        let since_epoch = self
+            .timestamp
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map_err(|e| Error::InvalidArgument(e.to_string()))?;
+
+        let fraction = match precision {
+            TimestampPrecision::Micro => since_epoch.subsec_micros(),
+            TimestampPrecision::Nano => since_epoch.subsec_nanos(),
+        };
+
+        let mut record = Vec::with_capacity(16 + self.data.len());
+        record.write_u32::<B>(since_epoch.as_secs() as u32)?;
+        record.write_u32::<B>(fraction)?;
+        record.write_u32::<B>(self.data.len() as u32)?;
+        record.write_u32::<B>(self.actual_length)?;
+        record.extend_from_slice(&self.data);
+
+        Ok(record)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use byteorder::ReadBytesExt;
+    use std::time::Duration;
+
+    #[test]
+    fn into_pcap_record_writes_nanosecond_fraction_under_nano_precision() {
+        let packet = Packet::new(
+            SystemTime::UNIX_EPOCH + Duration::from_secs(1) + Duration::from_nanos(123_456_789),
+            4,
+            4,
+            vec![1, 2, 3, 4],
+        );
+
+        let record = packet
+            .into_pcap_record::<byteorder::BigEndian>(TimestampPrecision::Nano)
+            .expect("Failed to convert to pcap record");
+
+        let mut cursor = std::io::Cursor::new(record);
+        let ts_sec = cursor
+            .read_u32::<byteorder::BigEndian>()
+            .expect("Failed to read ts_sec");
+        let ts_fraction = cursor
+            .read_u32::<byteorder::BigEndian>()
+            .expect("Failed to read ts_fraction");
+
+        assert_eq!(ts_sec, 1);
+        assert_eq!(ts_fraction, 123_456_789);
+    }
+
+    #[test]
+    fn into_pcap_record_writes_microsecond_fraction_under_micro_precision() {
+        let packet = Packet::new(
+            SystemTime::UNIX_EPOCH + Duration::from_secs(1) + Duration::from_nanos(123_456_789),
+            4,
+            4,
+            vec![1, 2, 3, 4],
+        );
+
+        let record = packet
+            .into_pcap_record::<byteorder::BigEndian>(TimestampPrecision::Micro)
+            .expect("Failed to convert to pcap record");
+
+        let mut cursor = std::io::Cursor::new(record);
+        let ts_sec = cursor
+            .read_u32::<byteorder::BigEndian>()
+            .expect("Failed to read ts_sec");
+        let ts_fraction = cursor
+            .read_u32::<byteorder::BigEndian>()
+            .expect("Failed to read ts_fraction");
+
+        assert_eq!(ts_sec, 1);
+        // subsec_micros() truncates, not rounds: 123_456_789ns -> 123_456us.
+        assert_eq!(ts_fraction, 123_456);
+    }
+}
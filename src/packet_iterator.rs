@@ -1,3 +1,4 @@
+use crate::config::TimestampPrecision;
 use crate::{Config, Error, Handle, Packet};
 
 use log::*;
@@ -8,7 +9,24 @@ use std::sync::Arc;
 use std::task::{Context, Poll};
 use std::time::Duration;
 use tokio::task;
-use failure::_core::cmp::max;
+
+/// User data threaded through `pcap_dispatch` by raw pointer: the batch being
+/// built up plus the precision needed to interpret `ts.tv_usec` correctly.
+struct DispatchContext {
+    packets: Vec<Packet>,
+    precision: TimestampPrecision,
+}
+
+fn packet_timestamp(
+    header: &pcap_sys::pcap_pkthdr,
+    precision: TimestampPrecision,
+) -> std::time::SystemTime {
+    let fraction = match precision {
+        TimestampPrecision::Micro => std::time::Duration::from_micros(header.ts.tv_usec as u64),
+        TimestampPrecision::Nano => std::time::Duration::from_nanos(header.ts.tv_usec as u64),
+    };
+    std::time::SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(header.ts.tv_sec as u64) + fraction
+}
 
 extern "C" fn dispatch_callback(
     user: *mut u8,
@@ -19,15 +37,13 @@ extern "C" fn dispatch_callback(
         warn!("Invalid data passed to callback");
     } else {
         unsafe {
-            let pending = std::mem::transmute::<*mut u8, &mut Vec<Packet>>(user);
-            let ts = std::time::SystemTime::UNIX_EPOCH
-                + std::time::Duration::from_secs((*header).ts.tv_sec as u64)
-                + std::time::Duration::from_micros((*header).ts.tv_usec as u64);
+            let context = std::mem::transmute::<*mut u8, &mut DispatchContext>(user);
+            let ts = packet_timestamp(&*header, context.precision);
             let length = (*header).caplen as usize;
             let mut data_vec = vec![0u8; length];
             std::ptr::copy(data, data_vec.as_mut_ptr(), length);
             let record = Packet::new(ts, (*header).caplen, (*header).len, data_vec);
-            pending.push(record)
+            context.packets.push(record)
         }
     }
 }
@@ -36,6 +52,7 @@ pub struct PacketIterator {
     pcap_handle: Arc<Handle>,
     max_packets_read: usize,
     live_capture: bool,
+    precision: TimestampPrecision,
     is_complete: bool,
 }
 
@@ -45,6 +62,13 @@ impl PacketIterator {
             pcap_handle: Arc::clone(handle),
             max_packets_read: config.max_packets_read(),
             live_capture: handle.is_live_capture(),
+            // Offline handles report microsecond timestamps regardless of the
+            // requested precision, which is only applied to live captures.
+            precision: if handle.is_live_capture() {
+                config.tstamp_precision()
+            } else {
+                TimestampPrecision::Micro
+            },
             is_complete: false,
         }
     }
@@ -61,6 +85,7 @@ fn dispatch_ex(
     pcap_handle: Arc<Handle>,
     live_capture: bool,
     max_packets_read: usize,
+    precision: TimestampPrecision,
 ) -> PacketIteratorItem {
     let mut packets = Vec::with_capacity(2 * max_packets_read);
     let mut header: *mut pcap_sys::pcap_pkthdr = std::ptr::null_mut();
@@ -109,9 +134,7 @@ fn dispatch_ex(
                     warn!("Invalid data passed to callback");
                 } else {
                     let record = unsafe {
-                        let ts = std::time::SystemTime::UNIX_EPOCH
-                            + std::time::Duration::from_secs((*header).ts.tv_sec as u64)
-                            + std::time::Duration::from_micros((*header).ts.tv_usec as u64);
+                        let ts = packet_timestamp(&*header, precision);
                         let length = (*header).caplen as usize;
                         let mut data_vec = vec![0u8; length];
                         std::ptr::copy(data, data_vec.as_mut_ptr(), length);
@@ -148,8 +171,12 @@ fn dispatch(
     pcap_handle: Arc<Handle>,
     live_capture: bool,
     max_packets_read: usize,
+    precision: TimestampPrecision,
 ) -> PacketIteratorItem {
-    let mut packets = Vec::with_capacity(2 * max_packets_read);
+    let mut context = DispatchContext {
+        packets: Vec::with_capacity(2 * max_packets_read),
+        precision,
+    };
 
     while !pcap_handle.interrupted() {
         let ret_code = unsafe {
@@ -157,7 +184,7 @@ fn dispatch(
                 pcap_handle.as_mut_ptr(),
                 max_packets_read as _,
                 Some(dispatch_callback),
-                &mut packets as *mut Vec<Packet> as *mut u8,
+                &mut context as *mut DispatchContext as *mut u8,
             )
         };
 
@@ -174,7 +201,7 @@ fn dispatch(
                 return PacketIteratorItem::Err(err);
             }
             0 => {
-                if packets.is_empty() {
+                if context.packets.is_empty() {
                     trace!("No packets in buffer");
                     return PacketIteratorItem::NoPackets;
                 } else {
@@ -182,18 +209,21 @@ fn dispatch(
                         debug!("Not live capture, calling breakloop");
                         unsafe { pcap_sys::pcap_breakloop(pcap_handle.as_mut_ptr()) }
                     }
-                    trace!("Capture loop captured {} available packets", packets.len());
-                    return PacketIteratorItem::Packets(packets);
+                    trace!(
+                        "Capture loop captured {} available packets",
+                        context.packets.len()
+                    );
+                    return PacketIteratorItem::Packets(context.packets);
                 }
             }
             x if x > 0 => {
                 trace!("Capture loop captured {} packets", x);
-                if packets.len() >= max_packets_read {
+                if context.packets.len() >= max_packets_read {
                     debug!(
                         "Capture loop captured up to maximum packets of {}",
                         max_packets_read
                     );
-                    return PacketIteratorItem::Packets(packets);
+                    return PacketIteratorItem::Packets(context.packets);
                 }
             }
             _ => {
@@ -206,10 +236,10 @@ fn dispatch(
 
     debug!("Interrupt invoked");
 
-    if packets.is_empty() {
+    if context.packets.is_empty() {
         PacketIteratorItem::Complete
     } else {
-        PacketIteratorItem::Packets(packets)
+        PacketIteratorItem::Packets(context.packets)
     }
 }
 
@@ -225,8 +255,56 @@ impl Iterator for PacketIterator {
             self.pcap_handle.clone(),
             self.live_capture,
             self.max_packets_read,
+            self.precision,
         );
 
         Some(r)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn pkthdr(tv_sec: i64, tv_usec: i64) -> pcap_sys::pcap_pkthdr {
+        pcap_sys::pcap_pkthdr {
+            ts: pcap_sys::timeval {
+                tv_sec: tv_sec as _,
+                tv_usec: tv_usec as _,
+            },
+            caplen: 0,
+            len: 0,
+        }
+    }
+
+    #[test]
+    fn packet_timestamp_interprets_micro_field_as_microseconds() {
+        let header = pkthdr(1_600_000_000, 500_000);
+        let ts = packet_timestamp(&header, TimestampPrecision::Micro);
+        assert_eq!(
+            ts,
+            std::time::SystemTime::UNIX_EPOCH
+                + Duration::from_secs(1_600_000_000)
+                + Duration::from_micros(500_000)
+        );
+    }
+
+    #[test]
+    fn packet_timestamp_interprets_nano_field_as_nanoseconds() {
+        let header = pkthdr(1_600_000_000, 500_000);
+        let ts = packet_timestamp(&header, TimestampPrecision::Nano);
+        assert_eq!(
+            ts,
+            std::time::SystemTime::UNIX_EPOCH
+                + Duration::from_secs(1_600_000_000)
+                + Duration::from_nanos(500_000)
+        );
+        assert_ne!(
+            ts,
+            std::time::SystemTime::UNIX_EPOCH
+                + Duration::from_secs(1_600_000_000)
+                + Duration::from_micros(500_000)
+        );
+    }
+}
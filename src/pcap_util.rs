@@ -0,0 +1,12 @@
+use crate::errors::Error;
+
+use std::ffi::CStr;
+
+/// Reads `pcap_geterr` off a handle and wraps it as a crate `Error`.
+pub fn convert_libpcap_error(handle: *mut pcap_sys::pcap_t) -> Error {
+    let msg = unsafe {
+        let err = pcap_sys::pcap_geterr(handle);
+        CStr::from_ptr(err).to_string_lossy().into_owned()
+    };
+    Error::LibPcapError(msg)
+}
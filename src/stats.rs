@@ -0,0 +1,32 @@
+/// A snapshot of libpcap's `pcap_stats` capture counters.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Stats {
+    received: u32,
+    dropped: u32,
+    if_dropped: u32,
+}
+
+impl Stats {
+    pub fn new(received: u32, dropped: u32, if_dropped: u32) -> Stats {
+        Stats {
+            received,
+            dropped,
+            if_dropped,
+        }
+    }
+
+    /// Packets received since the capture started.
+    pub fn received(&self) -> u32 {
+        self.received
+    }
+
+    /// Packets dropped because the kernel capture buffer overflowed.
+    pub fn dropped(&self) -> u32 {
+        self.dropped
+    }
+
+    /// Packets dropped by the network interface itself, not by libpcap.
+    pub fn if_dropped(&self) -> u32 {
+        self.if_dropped
+    }
+}
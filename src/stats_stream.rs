@@ -0,0 +1,83 @@
+use crate::errors::Error;
+use crate::handle::Handle;
+use crate::stats::Stats;
+
+use futures::stream::Stream;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tokio::time::{self, Interval};
+
+/// Periodically samples `pcap_stats` on a live handle, surfacing capture
+/// drop counters (kernel buffer overflow, interface drops) as an async
+/// stream that can run alongside the `PacketStream` for the same handle.
+pub struct StatsStream {
+    handle: Arc<Handle>,
+    interval: Interval,
+    errored: bool,
+}
+
+impl StatsStream {
+    pub fn new(interval: Duration, handle: Arc<Handle>) -> StatsStream {
+        StatsStream {
+            handle,
+            interval: time::interval(interval),
+            errored: false,
+        }
+    }
+}
+
+impl Stream for StatsStream {
+    type Item = Result<Stats, Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = unsafe { self.get_unchecked_mut() };
+
+        if this.errored || this.handle.interrupted() {
+            return Poll::Ready(None);
+        }
+
+        futures::ready!(Pin::new(&mut this.interval).poll_tick(cx));
+
+        let stats = this.handle.stats();
+        if stats.is_err() {
+            // Mirror PacketStream/capture_worker: stop after the first error
+            // instead of retrying pcap_stats forever on a broken handle.
+            this.errored = true;
+        }
+
+        Poll::Ready(Some(stats))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::handle::Handle;
+
+    use std::path::PathBuf;
+
+    fn noop_cx<'a>() -> Context<'a> {
+        Context::from_waker(futures::task::noop_waker_ref())
+    }
+
+    #[tokio::test]
+    async fn terminates_once_the_handle_is_interrupted() {
+        let pcap_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("resources")
+            .join("canary.pcap");
+
+        let handle = Handle::file_capture(pcap_path.to_str().expect("No path found"))
+            .expect("No handle created");
+        handle.interrupt();
+
+        let mut stream = StatsStream::new(Duration::from_millis(1), handle);
+        let mut cx = noop_cx();
+
+        assert!(matches!(
+            Pin::new(&mut stream).poll_next(&mut cx),
+            Poll::Ready(None)
+        ));
+    }
+}
@@ -1,23 +1,20 @@
-use crate::config::Config;
+use crate::capture_worker::{self, CaptureReceiver};
+use crate::config::{Config, TimestampPrecision};
 use crate::errors::Error;
 use crate::handle::Handle;
 use crate::packet::Packet;
-use crate::packet_future::PacketFuture;
 use crate::pcap_util;
 
 use futures::stream;
 use futures::stream::{Stream, StreamExt, Fuse};
 use log::*;
-use std::collections::VecDeque;
 use std::future::Future;
 use std::pin::Pin;
 use std::sync::Arc;
 use std::task::{Context, Poll};
 
 pub struct PacketStream {
-    config: Config,
-    handle: Arc<Handle>,
-    pending: Option<PacketFuture>,
+    receiver: CaptureReceiver,
 }
 
 impl PacketStream {
@@ -40,8 +37,17 @@ impl PacketStream {
                 .set_non_block()?
                 .set_promiscuous()?
                 .set_timeout(config.timeout())?
-                .set_buffer_size(config.buffer_size())?
-                .activate()?;
+                .set_buffer_size(config.buffer_size())?;
+
+            if config.tstamp_precision() == TimestampPrecision::Nano {
+                handle.set_tstamp_precision(TimestampPrecision::Nano)?;
+            }
+
+            handle.activate()?;
+
+            if let Some(dlt) = config.datalink() {
+                handle.set_datalink(dlt)?;
+            }
 
             if let Some(bpf) = config.bpf() {
                 let bpf = handle.compile_bpf(bpf)?;
@@ -49,11 +55,9 @@ impl PacketStream {
             }
         }
 
-        Ok(PacketStream {
-            config: config,
-            handle: handle,
-            pending: None,
-        })
+        let receiver = capture_worker::spawn(&config, Arc::clone(&handle))?;
+
+        Ok(PacketStream { receiver })
     }
 }
 
@@ -61,26 +65,15 @@ impl Stream for PacketStream {
     type Item = Result<Vec<Packet>, Error>;
 
     fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
-        let Self {
-            config,
-            handle,
-            pending,
-        } = unsafe { self.get_unchecked_mut() };
-
-        if pending.is_none() {
-            *pending = Some(PacketFuture::new(config, handle))
-        }
-        let p = pending.as_mut().unwrap();
-        let pin_pending = unsafe { Pin::new_unchecked(p) };
-        let packets = futures::ready!(pin_pending.poll(cx));
-        *pending = None;
-        let r = match packets {
-            Err(e) => Some(Err(e)),
-            Ok(None) => {
+        let Self { receiver } = unsafe { self.get_unchecked_mut() };
+
+        let r = match futures::ready!(receiver.poll_recv(cx)) {
+            None => {
                 debug!("Pcap stream complete");
                 None
             }
-            Ok(Some(p)) => {
+            Some(Err(e)) => Some(Err(e)),
+            Some(Ok(p)) => {
                 debug!("Pcap stream produced {} packets", p.len());
                 Some(Ok(p))
             }
@@ -89,71 +82,6 @@ impl Stream for PacketStream {
     }
 }
 
-/*
-impl<St1, St2> Stream for Select<St1, St2>
-    where St1: Stream,
-          St2: Stream<Item = St1::Item>
-{
-    type Item = St1::Item;
-
-    fn poll_next(
-        self: Pin<&mut Self>,
-        cx: &mut Context<'_>,
-    ) -> Poll<Option<St1::Item>> {
-        let Select { flag, stream1, stream2 } =
-            unsafe { self.get_unchecked_mut() };
-        let stream1 = unsafe { Pin::new_unchecked(stream1) };
-        let stream2 = unsafe { Pin::new_unchecked(stream2) };
-
-        if !*flag {
-            poll_inner(flag, stream1, stream2, cx)
-        } else {
-            poll_inner(flag, stream2, stream1, cx)
-        }
-    }
-}*/
-struct BridgedStream<St>
-{
-    streams: VecDeque<St>
-}
-
-
-impl<St: Stream<Item = Result<Vec<Packet>, Error>> + Unpin> Stream for BridgedStream<St> { //where St: Stream<Item = Result<Vec<Packet>, Error>> {
-    type Item = Result<Vec<Packet>, Error>;
-
-    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
-        let mut this = unsafe { self.get_unchecked_mut() };
-        let size = this.streams.len();
-        let mut buffer = vec![];
-        for _ in 0..size {
-            let current_stream_option = this.streams.pop_front();
-            match current_stream_option {
-                Some(mut current_stream) => {
-                    let blah = current_stream.size_hint();
-                    let current_value = Pin::new(&mut current_stream).poll_next(cx);
-                    // match current_value {
-                    //     Poll::Pending => {
-        
-                    //     }
-                    //     _ => {
-        
-                    //     }
-                    // }
-                }
-                None => {
-
-                }
-
-
-            }
-
-        }
-        
-        Poll::Ready(None)
-
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -192,7 +120,7 @@ mod tests {
 
         let packet = packets.first().cloned().expect("No packets");
         let data = packet
-            .into_pcap_record::<byteorder::BigEndian>()
+            .into_pcap_record::<byteorder::BigEndian>(crate::config::TimestampPrecision::Micro)
             .expect("Failed to convert to pcap record");
         let mut cursor = Cursor::new(data);
         let ts_sec = cursor